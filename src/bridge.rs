@@ -16,6 +16,14 @@ pub mod ffi {
 
         // close a table by id.
         pub fn close_table(id: u64);
+
+        // Note: SSTableIter::verify()/set_quarantine_mode() (consistency
+        // check + corrupt-tail quarantine) are library-only. An earlier
+        // pass added a verify_table entry here, but it couldn't link —
+        // there's no core::bridge backing function, since table/manifest
+        // aren't part of this source tree — so it was reverted. The MySQL
+        // side has no way to trigger or read back a check through this
+        // bridge yet.
     }
 
     // C++ types and signatures exposed to Rust.
@@ -0,0 +1,271 @@
+use crate::utils::*;
+
+/// Target uncompressed size of a single SSTable data block.
+pub const BLOCK_SIZE: usize = 4 * 1024;
+
+/// Entries between restart points inside a data block.
+pub const RESTART_INTERVAL: usize = 16;
+
+/// Location of a block (data or index) within the SSTable file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlockHandle {
+    pub offset: u64,
+    pub len: u32,
+}
+
+/// Maps the last key of a data block to where that block lives on disk.
+#[derive(Debug, Clone, Copy)]
+pub struct IndexEntry {
+    pub last_key: Key,
+    pub handle: BlockHandle,
+}
+
+fn write_varint(buf: &mut Vec<u8>, mut value: u64) {
+    loop {
+        let mut byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value != 0 {
+            byte |= 0x80;
+        }
+        buf.push(byte);
+        if value == 0 {
+            break;
+        }
+    }
+}
+
+fn read_varint(buf: &[u8]) -> (u64, usize) {
+    let mut value = 0u64;
+    let mut shift = 0u32;
+    let mut cursor = 0usize;
+    loop {
+        let byte = buf[cursor];
+        cursor += 1;
+        value |= ((byte & 0x7f) as u64) << shift;
+        if byte & 0x80 == 0 {
+            break;
+        }
+        shift += 7;
+    }
+    (value, cursor)
+}
+
+/// Accumulates entries for one data block: a restart point every
+/// [`RESTART_INTERVAL`] entries, then the restart offsets and their count.
+///
+/// See the [`super`] module docs for the caveat that nothing in this tree
+/// calls this yet.
+#[derive(Debug, Default)]
+pub struct DataBlockBuilder {
+    buf: Vec<u8>,
+    restarts: Vec<u32>,
+    count: usize,
+}
+
+impl DataBlockBuilder {
+    pub fn push(&mut self, entry: &KvStore) -> Result<()> {
+        if self.count % RESTART_INTERVAL == 0 {
+            self.restarts.push(self.buf.len() as u32);
+        }
+
+        let encoded = bincode::encode_to_vec(entry, BIN_CODE_CONF)?;
+        write_varint(&mut self.buf, encoded.len() as u64);
+        self.buf.extend_from_slice(&encoded);
+
+        self.count += 1;
+        Ok(())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    pub fn len(&self) -> usize {
+        self.buf.len()
+    }
+
+    /// Serializes the block, appending its restart array and count.
+    pub fn finish(mut self) -> Vec<u8> {
+        for offset in &self.restarts {
+            self.buf.extend_from_slice(&offset.to_le_bytes());
+        }
+        self.buf
+            .extend_from_slice(&(self.restarts.len() as u32).to_le_bytes());
+        self.buf
+    }
+}
+
+/// A decoded data block, ready for restart-assisted key lookup.
+#[derive(Debug)]
+pub struct DataBlock {
+    raw: Vec<u8>,
+    restarts: Vec<u32>,
+}
+
+impl DataBlock {
+    pub fn decode(mut raw: Vec<u8>) -> Result<Self> {
+        if raw.len() < 4 {
+            return Ok(Self {
+                raw,
+                restarts: Vec::new(),
+            });
+        }
+
+        let restart_count =
+            u32::from_le_bytes(raw[raw.len() - 4..].try_into().unwrap()) as usize;
+
+        // The restart array plus its trailing count must fit before the
+        // end of the block; a corrupt or truncated block can claim a
+        // count that doesn't, so check before subtracting into a usize.
+        if restart_count * 4 + 4 > raw.len() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!(
+                    "data block claims {restart_count} restarts, too many for a {}-byte block",
+                    raw.len()
+                ),
+            )
+            .into());
+        }
+
+        let restarts_start = raw.len() - 4 - restart_count * 4;
+
+        let restarts = raw[restarts_start..raw.len() - 4]
+            .chunks_exact(4)
+            .map(|chunk| u32::from_le_bytes(chunk.try_into().unwrap()))
+            .collect();
+
+        raw.truncate(restarts_start);
+        Ok(Self { raw, restarts })
+    }
+
+    fn decode_entry(&self, offset: usize) -> Result<(KvStore, usize)> {
+        let mut cursor = offset;
+        let (len, consumed) = read_varint(&self.raw[cursor..]);
+        cursor += consumed;
+
+        let len = len as usize;
+        let (entry, _) =
+            bincode::decode_from_slice::<KvStore, BincodeConfig>(&self.raw[cursor..cursor + len], BIN_CODE_CONF)?;
+        cursor += len;
+
+        Ok((entry, cursor))
+    }
+
+    /// Binary-searches the restart points for the last one at or before
+    /// `key`, then scans forward from there.
+    pub fn find(&self, key: Key) -> Result<Option<KvStore>> {
+        let mut lo = 0usize;
+        let mut hi = self.restarts.len();
+
+        while lo + 1 < hi {
+            let mid = (lo + hi) / 2;
+            let (entry, _) = self.decode_entry(self.restarts[mid] as usize)?;
+            if entry.0 <= key {
+                lo = mid;
+            } else {
+                hi = mid;
+            }
+        }
+
+        let mut cursor = self.restarts.get(lo).copied().unwrap_or(0) as usize;
+        while cursor < self.raw.len() {
+            let (entry, next) = self.decode_entry(cursor)?;
+            if entry.0 == key {
+                return Ok(Some(entry));
+            }
+            if entry.0 > key {
+                return Ok(None);
+            }
+            cursor = next;
+        }
+
+        Ok(None)
+    }
+}
+
+/// Maps each data block's last key to its on-disk handle.
+#[derive(Debug, Default)]
+pub struct IndexBlock {
+    entries: Vec<IndexEntry>,
+}
+
+impl IndexBlock {
+    pub fn push(&mut self, last_key: Key, handle: BlockHandle) {
+        self.entries.push(IndexEntry { last_key, handle });
+    }
+
+    pub fn encode(&self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(self.entries.len() * 20);
+        for entry in &self.entries {
+            buf.extend_from_slice(&entry.last_key.to_le_bytes());
+            buf.extend_from_slice(&entry.handle.offset.to_le_bytes());
+            buf.extend_from_slice(&entry.handle.len.to_le_bytes());
+        }
+        buf
+    }
+
+    pub fn decode(raw: &[u8]) -> Self {
+        const ENTRY_SIZE: usize = 8 + 8 + 4;
+        let entries = raw
+            .chunks_exact(ENTRY_SIZE)
+            .map(|chunk| IndexEntry {
+                last_key: Key::from_le_bytes(chunk[0..8].try_into().unwrap()),
+                handle: BlockHandle {
+                    offset: u64::from_le_bytes(chunk[8..16].try_into().unwrap()),
+                    len: u32::from_le_bytes(chunk[16..20].try_into().unwrap()),
+                },
+            })
+            .collect();
+        Self { entries }
+    }
+
+    /// Finds the first data block whose last key is `>= key`.
+    pub fn find(&self, key: Key) -> Option<&IndexEntry> {
+        let idx = self.entries.partition_point(|entry| entry.last_key < key);
+        self.entries.get(idx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn varint_round_trips_small_and_large_values() {
+        for value in [0u64, 1, 127, 128, 300, u32::MAX as u64, u64::MAX] {
+            let mut buf = Vec::new();
+            write_varint(&mut buf, value);
+            let (decoded, consumed) = read_varint(&buf);
+            assert_eq!(decoded, value);
+            assert_eq!(consumed, buf.len());
+        }
+    }
+
+    #[test]
+    fn index_block_find_picks_first_handle_covering_key() {
+        let mut index = IndexBlock::default();
+        index.push(10, BlockHandle { offset: 0, len: 100 });
+        index.push(20, BlockHandle { offset: 100, len: 100 });
+        index.push(30, BlockHandle { offset: 200, len: 100 });
+
+        assert_eq!(index.find(5).unwrap().last_key, 10);
+        assert_eq!(index.find(10).unwrap().last_key, 10);
+        assert_eq!(index.find(11).unwrap().last_key, 20);
+        assert_eq!(index.find(30).unwrap().last_key, 30);
+        assert!(index.find(31).is_none());
+    }
+
+    #[test]
+    fn index_block_encode_decode_round_trips() {
+        let mut index = IndexBlock::default();
+        index.push(10, BlockHandle { offset: 0, len: 100 });
+        index.push(20, BlockHandle { offset: 100, len: 50 });
+
+        let decoded = IndexBlock::decode(&index.encode());
+
+        assert_eq!(decoded.find(15).unwrap().last_key, 20);
+        assert_eq!(decoded.find(15).unwrap().handle.offset, 100);
+        assert_eq!(decoded.find(15).unwrap().handle.len, 50);
+    }
+}
@@ -0,0 +1,21 @@
+//! Block, index, filter and codec primitives for the `.yyt` SSTable format.
+//!
+//! This module is the read side only: `DataBlock`/`IndexBlock` lookup,
+//! `BloomFilter` membership tests, and per-table `Codec` dispatch are all
+//! implemented and tested in isolation, but nothing in this tree builds a
+//! `.yyt` file with any of them. SSTable creation lives in
+//! `manifest`/`table`/`sstable`, none of which are part of this source
+//! tree, so every file this codebase can currently produce still has
+//! `index_len == 0` and `filter_len == 0`, whatever codec tag a legacy
+//! upgrade happens to stamp on it. Until a writer calls
+//! `DataBlockBuilder::push`, `IndexBlock::push` and `BloomFilter::build`
+//! and picks a codec per table, `seek` always falls back to the full
+//! linear scan and `may_contain` always returns `true` — the O(log n)
+//! lookups, pruning, and per-tier codec choice this module enables aren't
+//! reachable yet. Treat it as scaffolding for that writer, not a shipped
+//! feature.
+
+pub mod block;
+pub mod bloom;
+pub mod codec;
+pub mod sstable_iter;
@@ -1,19 +1,59 @@
 use bincode::error::DecodeError;
 use crc32fast::Hasher;
 use futures::Future;
-use std::{collections::VecDeque, io::SeekFrom};
+use std::{
+    collections::VecDeque,
+    io::SeekFrom,
+    path::{Path, PathBuf},
+};
 use tokio::{
     fs::File,
-    io::{AsyncReadExt, AsyncSeekExt, BufReader},
+    io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt, BufReader},
 };
 
 use crate::{
-    structs::{AsyncIterator, SSTABLE_MAGIC_NUMBER},
+    structs::{
+        lsm::block::{BlockHandle, DataBlock, IndexBlock},
+        lsm::bloom::BloomFilter,
+        lsm::codec::{decoder_for, Codec, SSTableDecoder},
+        AsyncIterator, SSTABLE_MAGIC_NUMBER,
+    },
     utils::*,
 };
 
 pub const SSTABLE_ITER_BUF_SIZE: usize = 0x800;
-const HEADER_SIZE: u64 = 36;
+
+/// Header size of the current format: signature + version byte, codec tag,
+/// index block and filter block.
+const HEADER_SIZE: u64 = 66;
+
+/// 8-byte file signature, PNG-style: a non-ASCII first byte to catch 7-bit
+/// stripping transfers, a recognizable "YYT" tag, and a CR-LF/EOF pair to
+/// catch line-ending mangling.
+const SSTABLE_SIGNATURE: [u8; 8] = [0x89, b'Y', b'Y', b'T', b'\r', b'\n', 0x1a, b'\n'];
+
+/// Current on-disk format: signature + version byte, codec tag, index
+/// block and filter block.
+const FORMAT_VERSION: u8 = 1;
+
+/// Header size of the original format: bare 4-byte magic number, no
+/// version byte, no codec/index/filter blocks.
+const LEGACY_HEADER_SIZE: u64 = 36;
+
+/// Result of [`SSTableIter::verify`]: whether the file's on-disk checksums
+/// and entry count match what it actually contains.
+#[derive(Debug, Clone)]
+pub struct VerifyReport {
+    pub file: PathBuf,
+    pub entries_count: u32,
+    pub deleted_count: u32,
+    pub min_key: Key,
+    pub max_key: Key,
+    pub raw_checksum_ok: bool,
+    pub compressed_checksum_ok: bool,
+    pub entries_decoded: u32,
+    pub corrupt: bool,
+}
 
 #[derive(Debug)]
 pub struct SSTableIter {
@@ -27,9 +67,18 @@ pub struct SSTableIter {
     buf: VecDeque<u8>,
     raw_checksum: u32,
     compressed_checksum: u32,
+    codec: Codec,
+    legacy: bool,
     min_key: Key,
     max_key: Key,
-    reader: Option<CompressionDecoder<BufReader<File>>>,
+    index_offset: u64,
+    index_len: u32,
+    filter_offset: u64,
+    filter_len: u32,
+    quarantine_corrupt: bool,
+    resyncing: bool,
+    corrupted_entries: u32,
+    reader: Option<SSTableDecoder<BufReader<File>>>,
 }
 
 impl SSTableIter {
@@ -43,8 +92,17 @@ impl SSTableIter {
             last_entry_key: None,
             compressed_checksum: 0,
             bytes_read: 0,
+            codec: Codec::Zstd,
+            legacy: false,
             min_key: 0,
             max_key: 0,
+            index_offset: 0,
+            index_len: 0,
+            filter_offset: 0,
+            filter_len: 0,
+            quarantine_corrupt: false,
+            resyncing: false,
+            corrupted_entries: 0,
             hasher: None,
             buf: VecDeque::with_capacity(data_size as usize * 2),
             reader: None,
@@ -60,34 +118,318 @@ impl SSTableIter {
         self.io.clone().await
     }
 
+    /// Enables or disables quarantine mode: when enabled, a decode error
+    /// part-way through the entry stream no longer aborts the iterator.
+    /// Instead `next` resyncs byte-by-byte past the corrupt tail, counting
+    /// each corrupt stretch it skips, so a single corrupt tail doesn't render
+    /// an otherwise-good SSTable unreadable.
+    pub fn set_quarantine_mode(&mut self, enabled: bool) {
+        self.quarantine_corrupt = enabled;
+    }
+
+    /// Corrupt entry runs quarantine mode has resynced past since the
+    /// iterator was created (one per contiguous corrupt stretch, not one
+    /// per byte skipped).
+    pub fn corrupted_entries(&self) -> u32 {
+        self.corrupted_entries
+    }
+
+    /// Recomputes both CRC32s and replays the entry stream, mirroring
+    /// `tests::check_file` but as a callable API rather than a test-only
+    /// helper, so callers can detect a corrupt file instead of silently
+    /// serving bad rows from it.
+    ///
+    /// This only verifies the single file behind this iterator. Walking
+    /// every SSTable for a table id and aggregating the results belongs on
+    /// the table subsystem (`table`/`manifest`), neither of which are part
+    /// of this source tree, so that aggregator doesn't exist yet — callers
+    /// needing table-wide verification have to list a table's files
+    /// themselves and call this per file.
+    ///
+    /// This is library-only: there is no `bridge.rs` ffi entry for it, so
+    /// the MySQL side can't trigger or read back a check yet (see the note
+    /// there). Call this from Rust until a `core::bridge` backing function
+    /// exists to expose it.
+    pub async fn verify(&self) -> Result<VerifyReport> {
+        let mut file_io = self.io.inner().await?;
+        let file_len = file_io.metadata().await?.len();
+
+        // A legacy (pre-signature) file only has a 36-byte header with no
+        // index/filter blocks; which format applies was already decided by
+        // `recreate`, so reuse that instead of re-deriving it from the
+        // file's total length (which a legacy file can exceed once it has
+        // more than a couple of entries).
+        let header_size = if self.legacy {
+            LEGACY_HEADER_SIZE
+        } else {
+            HEADER_SIZE
+        };
+        file_io.seek(SeekFrom::Start(header_size)).await?;
+
+        let entry_stream_len = if self.filter_len > 0 && self.filter_offset >= header_size {
+            self.filter_offset - header_size
+        } else if self.index_len > 0 && self.index_offset >= header_size {
+            self.index_offset - header_size
+        } else {
+            file_len.saturating_sub(header_size)
+        };
+
+        let mut compressed = vec![0u8; entry_stream_len as usize];
+        file_io.read_exact(&mut compressed).await?;
+
+        let mut hasher = Hasher::new();
+        hasher.update(&compressed);
+        let compressed_checksum_ok = hasher.finalize() == self.compressed_checksum;
+
+        let mut raw = Vec::new();
+        decoder_for(self.codec, compressed.as_slice())?
+            .read_to_end(&mut raw)
+            .await?;
+
+        let mut hasher = Hasher::new();
+        hasher.update(&raw);
+        let raw_checksum_ok = hasher.finalize() == self.raw_checksum;
+
+        let mut offset = 0usize;
+        let mut entries_decoded = 0u32;
+        while offset < raw.len() && entries_decoded < self.entries_count {
+            match bincode::decode_from_slice::<KvStore, BincodeConfig>(&raw[offset..], BIN_CODE_CONF) {
+                Ok((_, consumed)) => {
+                    offset += consumed;
+                    entries_decoded += 1;
+                }
+                Err(_) => break,
+            }
+        }
+
+        let corrupt = !raw_checksum_ok
+            || !compressed_checksum_ok
+            || entries_decoded != self.entries_count;
+
+        if corrupt {
+            error!(
+                "SSTable failed verification: {:?} (raw_checksum_ok={}, compressed_checksum_ok={}, entries_decoded={}/{})",
+                self.io.file_path, raw_checksum_ok, compressed_checksum_ok, entries_decoded, self.entries_count
+            );
+        }
+
+        Ok(VerifyReport {
+            file: self.io.file_path.clone(),
+            entries_count: self.entries_count,
+            deleted_count: self.deleted_count,
+            min_key: self.min_key,
+            max_key: self.max_key,
+            raw_checksum_ok,
+            compressed_checksum_ok,
+            entries_decoded,
+            corrupt,
+        })
+    }
+
     pub async fn recreate(&mut self) -> Result<()> {
         let mut file_io = self.io.inner().await?;
 
-        if file_io.metadata().await?.len() < HEADER_SIZE {
+        if file_io.metadata().await?.len() < LEGACY_HEADER_SIZE {
             trace!("Empty Iter          : {:?}", self.io.file_path);
             return Ok(());
         }
 
         file_io.seek(SeekFrom::Start(0)).await?;
 
-        let magic_number = file_io.read_u32().await?;
+        // The legacy format opens with a bare 4-byte magic number; the
+        // current one opens with an 8-byte signature whose first 4 bytes
+        // never collide with it. Peek those 4 bytes to tell them apart.
+        let first_word = file_io.read_u32().await?;
+
+        if first_word == SSTABLE_MAGIC_NUMBER {
+            trace!("Legacy format Iter  : {:?}", self.io.file_path);
+
+            self.legacy = true;
+
+            self.raw_checksum = file_io.read_u32().await?;
+            self.compressed_checksum = file_io.read_u32().await?;
+            self.entries_count = file_io.read_u32().await?;
+            self.deleted_count = file_io.read_u32().await?;
+
+            self.codec = Codec::Zstd;
+
+            self.min_key = file_io.read_u64().await?;
+            self.max_key = file_io.read_u64().await?;
+
+            self.index_offset = 0;
+            self.index_len = 0;
+            self.filter_offset = 0;
+            self.filter_len = 0;
+
+            return Ok(());
+        }
+
+        file_io.seek(SeekFrom::Start(0)).await?;
+        let mut signature = [0u8; 8];
+        file_io.read_exact(&mut signature).await?;
 
-        if magic_number != SSTABLE_MAGIC_NUMBER {
+        if signature != SSTABLE_SIGNATURE {
             return Err(DbError::InvalidMagicNumber);
         }
 
-        self.raw_checksum = file_io.read_u32().await?;
-        self.compressed_checksum = file_io.read_u32().await?;
-        self.entries_count = file_io.read_u32().await?;
-        self.deleted_count = file_io.read_u32().await?;
+        let version = file_io.read_u8().await?;
+
+        match version {
+            FORMAT_VERSION => {
+                self.legacy = false;
+
+                self.raw_checksum = file_io.read_u32().await?;
+                self.compressed_checksum = file_io.read_u32().await?;
+                self.entries_count = file_io.read_u32().await?;
+                self.deleted_count = file_io.read_u32().await?;
+
+                self.codec = Codec::from_tag(file_io.read_u8().await?)?;
+
+                self.min_key = file_io.read_u64().await?;
+                self.max_key = file_io.read_u64().await?;
 
-        self.min_key = file_io.read_u64().await?;
-        self.max_key = file_io.read_u64().await?;
+                self.index_offset = file_io.read_u64().await?;
+                self.index_len = file_io.read_u32().await?;
+
+                self.filter_offset = file_io.read_u64().await?;
+                self.filter_len = file_io.read_u32().await?;
+            }
+            other => {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    format!("unsupported SSTable format version {other}"),
+                )
+                .into())
+            }
+        }
 
         trace!("Recreated Iter      : {:?}", self.io.file_path);
         Ok(())
     }
 
+    /// Rewrites a legacy (pre-signature) `.yyt` file in the current
+    /// format in place: the compressed entry stream is untouched, only the
+    /// header grows to the new signature + version + codec tag, with empty
+    /// index/filter blocks pointing past the entry stream.
+    ///
+    /// This is the per-file primitive a rolling upgrade needs, but nothing
+    /// in this tree calls it yet — a table-level sweep that walks every
+    /// SSTable and upgrades the legacy ones belongs in `manifest`, which
+    /// isn't part of this source tree. Until that sweep exists, legacy
+    /// files stay on the old format until something calls this explicitly;
+    /// `recreate` already reads either format, so callers aren't broken,
+    /// just not auto-migrated.
+    pub async fn upgrade_file(path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        let mut file = File::open(path).await?;
+
+        if file.read_u32().await? != SSTABLE_MAGIC_NUMBER {
+            // Not the legacy magic; confirm it's actually the current
+            // signature rather than silently treating any other garbage
+            // as "already upgraded".
+            file.seek(SeekFrom::Start(0)).await?;
+            let mut signature = [0u8; 8];
+            file.read_exact(&mut signature).await?;
+            if signature != SSTABLE_SIGNATURE {
+                return Err(DbError::InvalidMagicNumber);
+            }
+
+            trace!("Already current format, skipping upgrade: {:?}", path);
+            return Ok(());
+        }
+
+        file.seek(SeekFrom::Start(0)).await?;
+        let mut old_header = [0u8; LEGACY_HEADER_SIZE as usize];
+        file.read_exact(&mut old_header).await?;
+
+        let mut body = Vec::new();
+        file.read_to_end(&mut body).await?;
+        drop(file);
+
+        let body_end = HEADER_SIZE + body.len() as u64;
+
+        // Write the upgraded file to a sibling temp path and rename it over
+        // the original once it's fully flushed and synced, so a crash or
+        // write error partway through leaves the original file untouched
+        // instead of a truncated one.
+        let mut tmp_path = path.as_os_str().to_owned();
+        tmp_path.push(".upgrade_tmp");
+        let tmp_path = PathBuf::from(tmp_path);
+
+        let mut tmp_file = File::create(&tmp_path).await?;
+        tmp_file.write_all(&SSTABLE_SIGNATURE).await?;
+        tmp_file.write_u8(FORMAT_VERSION).await?;
+        tmp_file.write_all(&old_header[4..20]).await?; // raw/compressed checksums, entries/deleted counts
+        tmp_file.write_u8(Codec::Zstd.tag()).await?;
+        tmp_file.write_all(&old_header[20..36]).await?; // min_key, max_key
+        tmp_file.write_u64(body_end).await?; // index_offset (no index block)
+        tmp_file.write_u32(0).await?; // index_len
+        tmp_file.write_u64(body_end).await?; // filter_offset (no filter block)
+        tmp_file.write_u32(0).await?; // filter_len
+        tmp_file.write_all(&body).await?;
+        tmp_file.sync_all().await?;
+        drop(tmp_file);
+
+        tokio::fs::rename(&tmp_path, path).await?;
+
+        Ok(())
+    }
+
+    /// Looks up `key` via the index block in two small reads: one for the
+    /// data block handle, one for the block itself, instead of scanning
+    /// the whole entry stream. Falls back to a full forward scan for
+    /// legacy files that predate the index block.
+    pub async fn seek(&mut self, key: Key) -> Result<Option<KvStore>> {
+        if self.index_len == 0 {
+            trace!("No index block, falling back to full scan: [{}]", key);
+            self.init_iter().await?;
+            while let Some(entry) = AsyncIterator::next(self).await? {
+                if entry.0 == key {
+                    return Ok(Some(entry));
+                }
+                if entry.0 > key {
+                    break;
+                }
+            }
+            return Ok(None);
+        }
+
+        let mut file_io = self.io.inner().await?;
+
+        file_io.seek(SeekFrom::Start(self.index_offset)).await?;
+        let mut index_bytes = vec![0u8; self.index_len as usize];
+        file_io.read_exact(&mut index_bytes).await?;
+
+        let Some(handle) = find_block_handle(&index_bytes, key) else {
+            return Ok(None);
+        };
+
+        file_io.seek(SeekFrom::Start(handle.offset)).await?;
+        let mut block_bytes = vec![0u8; handle.len as usize];
+        file_io.read_exact(&mut block_bytes).await?;
+
+        decode_and_find_in_block(self.codec, &block_bytes, key).await
+    }
+
+    /// Tests the filter block for `key` without touching the compressed
+    /// entry stream. Returns `false` only when `key` is definitely absent;
+    /// `true` means "maybe present" (including for legacy files that have
+    /// no filter block, where the caller must fall back to scanning).
+    pub async fn may_contain(&self, key: Key) -> Result<bool> {
+        // A filter block is at least the 8-byte `m`/`k` prefix; anything
+        // shorter is not a real filter block, so don't let it skip the scan.
+        if self.filter_len < 8 {
+            return Ok(true);
+        }
+
+        let mut file_io = self.io.inner().await?;
+        file_io.seek(SeekFrom::Start(self.filter_offset)).await?;
+        let mut filter_bytes = vec![0u8; self.filter_len as usize];
+        file_io.read_exact(&mut filter_bytes).await?;
+
+        Ok(decode_filter_block(&filter_bytes).may_contain(key))
+    }
+
     pub async fn init_iter(&mut self) -> Result<()> {
         self.entry_cur = 0;
         self.hasher.replace(Hasher::new());
@@ -97,8 +439,12 @@ impl SSTableIter {
 
         let mut file = File::open(self.io.file_path.as_ref()).await?;
         file.seek(SeekFrom::Start(HEADER_SIZE)).await?;
+        if self.legacy {
+            // Legacy files have the shorter 36-byte header.
+            file.seek(SeekFrom::Start(LEGACY_HEADER_SIZE)).await?;
+        }
         self.reader
-            .replace(CompressionDecoder::new(BufReader::new(file)));
+            .replace(decoder_for(self.codec, BufReader::new(file))?);
 
         Ok(())
     }
@@ -139,6 +485,33 @@ impl SSTableIter {
     }
 }
 
+/// Finds the handle of the data block `key` could live in, from an already
+/// read-in-full index block. Split out of [`SSTableIter::seek`] so the
+/// lookup itself is testable without a file-backed `SSTableIter`.
+fn find_block_handle(index_bytes: &[u8], key: Key) -> Option<BlockHandle> {
+    IndexBlock::decode(index_bytes).find(key).map(|entry| entry.handle)
+}
+
+/// Decompresses an already read-in-full data block and searches it for
+/// `key`. Split out of [`SSTableIter::seek`] so the decode-and-search path
+/// is testable without a file-backed `SSTableIter`.
+async fn decode_and_find_in_block(codec: Codec, block_bytes: &[u8], key: Key) -> Result<Option<KvStore>> {
+    let mut raw = Vec::new();
+    decoder_for(codec, block_bytes)?.read_to_end(&mut raw).await?;
+    DataBlock::decode(raw)?.find(key)
+}
+
+/// Reconstructs the Bloom filter from an already read-in-full filter
+/// block (`m`, `k`, then the bit array). Split out of
+/// [`SSTableIter::may_contain`] so it's testable without a file-backed
+/// `SSTableIter`. Callers are expected to have already checked
+/// `filter_len >= 8`.
+fn decode_filter_block(filter_bytes: &[u8]) -> BloomFilter {
+    let m = u32::from_le_bytes(filter_bytes[0..4].try_into().unwrap());
+    let k = u32::from_le_bytes(filter_bytes[4..8].try_into().unwrap());
+    BloomFilter::from_parts(m, k, filter_bytes[8..].to_vec())
+}
+
 impl AsyncIterator<KvStore> for SSTableIter {
     type NextFuture<'a> = impl Future<Output = Result<Option<KvStore>>> + 'a;
 
@@ -195,6 +568,7 @@ impl AsyncIterator<KvStore> for SSTableIter {
                         self.entry_cur += 1;
                         self.buf.drain(..offset);
                         self.last_entry_key.replace(data_store.0);
+                        self.resyncing = false;
 
                         break data_store;
                     }
@@ -211,6 +585,21 @@ impl AsyncIterator<KvStore> for SSTableIter {
                                     .or_else(|_| Result::Ok("< cannot format >".to_string()))
                                     .unwrap()
                             );
+
+                            if self.quarantine_corrupt && !self.buf.is_empty() {
+                                if !self.resyncing {
+                                    warn!(
+                                        "Quarantining corrupt entry {} in file {}, resyncing",
+                                        self.entry_cur,
+                                        self.io.file_path.display()
+                                    );
+                                    self.resyncing = true;
+                                    self.corrupted_entries += 1;
+                                }
+                                self.buf.pop_front();
+                                continue;
+                            }
+
                             return Ok(None);
                         }
                     },
@@ -225,7 +614,6 @@ impl AsyncIterator<KvStore> for SSTableIter {
 #[cfg(test)]
 pub mod tests {
     use super::*;
-    use crate::structs::SSTABLE_MAGIC_NUMBER;
     use console::style;
     use indicatif::HumanBytes;
     use tokio::fs::File;
@@ -233,9 +621,16 @@ pub mod tests {
     pub async fn check_file(file_name: &str) -> Result<()> {
         let mut file = File::open(file_name).await?;
 
-        let magic_number = file.read_u32().await?;
+        let mut signature = [0u8; 8];
+        file.read_exact(&mut signature).await?;
 
-        if magic_number != SSTABLE_MAGIC_NUMBER {
+        if signature != SSTABLE_SIGNATURE {
+            return Err(DbError::InvalidMagicNumber);
+        }
+
+        let version = file.read_u8().await?;
+
+        if version != FORMAT_VERSION {
             return Err(DbError::InvalidMagicNumber);
         }
 
@@ -245,18 +640,35 @@ pub mod tests {
         let entries_count = file.read_u32().await?;
         let deleted = file.read_u32().await?;
 
+        let codec = Codec::from_tag(file.read_u8().await?)?;
+
         let min_key = file.read_u64().await?;
         let max_key = file.read_u64().await?;
 
+        let _index_offset = file.read_u64().await?;
+        let _index_len = file.read_u32().await?;
+
+        let filter_offset = file.read_u64().await?;
+        let filter_len = file.read_u32().await?;
+
+        let entry_stream_len = if filter_len > 0 {
+            filter_offset - HEADER_SIZE
+        } else {
+            u64::MAX
+        };
+
         let mut bytes = Vec::new();
-        let bytes_total = file.read_to_end(&mut bytes).await?;
+        let bytes_total = file
+            .take(entry_stream_len)
+            .read_to_end(&mut bytes)
+            .await?;
 
         let mut hasher = Hasher::new();
         hasher.update(&bytes);
         let computed_compressed_checksum = hasher.finalize();
 
         let mut raw = Vec::new();
-        CompressionDecoder::new(bytes.as_slice())
+        decoder_for(codec, bytes.as_slice())?
             .read_to_end(&mut raw)
             .await?;
 
@@ -301,4 +713,121 @@ pub mod tests {
 
         Ok(())
     }
+
+    /// Writes a synthetic legacy (pre-signature) `.yyt` file: bare 4-byte
+    /// magic number, 32 more header bytes, then `body`.
+    async fn write_legacy_file(path: &std::path::Path, body: &[u8]) {
+        let mut file = File::create(path).await.unwrap();
+        file.write_u32(SSTABLE_MAGIC_NUMBER).await.unwrap();
+        file.write_u32(0xdead_beef).await.unwrap(); // raw_checksum
+        file.write_u32(0xfeed_face).await.unwrap(); // compressed_checksum
+        file.write_u32(7).await.unwrap(); // entries_count
+        file.write_u32(1).await.unwrap(); // deleted_count
+        file.write_u64(10).await.unwrap(); // min_key
+        file.write_u64(20).await.unwrap(); // max_key
+        file.write_all(body).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn upgrade_file_preserves_checksums_and_moves_the_entry_stream() {
+        let path = std::env::temp_dir().join("yydb_upgrade_file_test.yyt");
+        // A legacy file with more than a couple of entries' worth of body,
+        // i.e. one whose total size exceeds the current HEADER_SIZE (66), so
+        // file size alone can't tell the two formats apart.
+        let body = vec![0x42u8; 200];
+        write_legacy_file(&path, &body).await;
+
+        super::SSTableIter::upgrade_file(&path).await.unwrap();
+
+        let mut file = File::open(&path).await.unwrap();
+        let mut signature = [0u8; 8];
+        file.read_exact(&mut signature).await.unwrap();
+        assert_eq!(signature, SSTABLE_SIGNATURE);
+
+        let version = file.read_u8().await.unwrap();
+        assert_eq!(version, FORMAT_VERSION);
+
+        assert_eq!(file.read_u32().await.unwrap(), 0xdead_beef); // raw_checksum
+        assert_eq!(file.read_u32().await.unwrap(), 0xfeed_face); // compressed_checksum
+        assert_eq!(file.read_u32().await.unwrap(), 7); // entries_count
+        assert_eq!(file.read_u32().await.unwrap(), 1); // deleted_count
+
+        let codec = Codec::from_tag(file.read_u8().await.unwrap()).unwrap();
+        assert_eq!(codec, Codec::Zstd);
+
+        assert_eq!(file.read_u64().await.unwrap(), 10); // min_key
+        assert_eq!(file.read_u64().await.unwrap(), 20); // max_key
+
+        let index_offset = file.read_u64().await.unwrap();
+        assert_eq!(file.read_u32().await.unwrap(), 0); // index_len
+        let filter_offset = file.read_u64().await.unwrap();
+        assert_eq!(file.read_u32().await.unwrap(), 0); // filter_len
+
+        assert_eq!(index_offset, HEADER_SIZE + body.len() as u64);
+        assert_eq!(filter_offset, HEADER_SIZE + body.len() as u64);
+
+        let mut rest = Vec::new();
+        file.read_to_end(&mut rest).await.unwrap();
+        assert_eq!(rest, body);
+
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[tokio::test]
+    async fn upgrade_file_rejects_a_file_that_is_neither_format() {
+        let path = std::env::temp_dir().join("yydb_upgrade_file_corrupt_test.yyt");
+        tokio::fs::write(&path, b"not an sstable at all, just garbage bytes")
+            .await
+            .unwrap();
+
+        let result = super::SSTableIter::upgrade_file(&path).await;
+        assert!(result.is_err());
+
+        // Left untouched, not silently reported as an already-upgraded no-op.
+        let contents = tokio::fs::read(&path).await.unwrap();
+        assert_eq!(contents, b"not an sstable at all, just garbage bytes");
+
+        std::fs::remove_file(&path).ok();
+    }
+
+    // `seek`/`may_contain` are inherent methods on a file-backed
+    // `SSTableIter`, which can't be constructed in a test here: its `io`
+    // field is an `IOHandler`, a type this source tree doesn't define (it
+    // lives in `utils`, which isn't part of this tree) and has no
+    // reachable constructor to call. These tests instead drive the same
+    // hand-written-bytes-to-result path through the free functions those
+    // methods delegate to, which is the part of "found / not found" that's
+    // actually at risk of a decode bug.
+
+    #[test]
+    fn filter_block_round_trip_finds_present_key_and_rejects_absent_key() {
+        let present: Vec<Key> = (0..200).collect();
+        let filter = BloomFilter::build(&present);
+
+        let mut filter_bytes = Vec::new();
+        filter_bytes.extend_from_slice(&filter.m().to_le_bytes());
+        filter_bytes.extend_from_slice(&filter.k().to_le_bytes());
+        filter_bytes.extend_from_slice(filter.bits());
+
+        let decoded = decode_filter_block(&filter_bytes);
+
+        assert!(decoded.may_contain(42));
+        // A key far outside the built range; with n=200 and the filter's
+        // target false-positive rate this isn't a realistic collision.
+        assert!(!decoded.may_contain(1_000_000));
+    }
+
+    #[test]
+    fn index_lookup_finds_covering_block_and_rejects_key_past_the_end() {
+        let mut index = IndexBlock::default();
+        index.push(10, BlockHandle { offset: HEADER_SIZE, len: 64 });
+        index.push(20, BlockHandle { offset: HEADER_SIZE + 64, len: 32 });
+        let index_bytes = index.encode();
+
+        let handle = find_block_handle(&index_bytes, 15).unwrap();
+        assert_eq!(handle.offset, HEADER_SIZE + 64);
+        assert_eq!(handle.len, 32);
+
+        assert!(find_block_handle(&index_bytes, 21).is_none());
+    }
 }
@@ -3,5 +3,6 @@ pub mod table;
 pub mod kvstore;
 pub mod sstable;
 pub mod manifest;
+pub mod lsm;
 
 const TABLE_FILE_SUFFIX: &str = ".yyt";